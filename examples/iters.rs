@@ -4,17 +4,37 @@ use file_structure::{File, FileType, FsError};
 fn main() -> Result<(), FsError> {
     let examples_folder = File::<()>::new_from_path("examples/", true)?;
 
+    // Fans subdirectory reads out across up to 4 threads instead of reading
+    // the whole tree on this one
+    let _examples_folder_parallel = File::<()>::new_from_path_parallel("examples/", 4)?;
+
     // Recursive iterator that starts at file `examples_folder`
     // See documentation to see how to apply filters to this FilesIter
     for file in examples_folder.files() {
-        println!("{:#?}", file);
+        println!("{:#?}", file?);
     }
 
     // Same, but using PathsIter
     for path in examples_folder.paths() {
-        // println!("{:?}", path);
+        // println!("{:?}", path?);
     }
 
+    // `follow_symlinks` descends into symlinked directories instead of
+    // treating them as leaves, surfacing `FsError::LoopDetected` if doing so
+    // would recurse forever
+    for file in examples_folder.files().follow_symlinks(true) {
+        match file {
+            Ok(file) => println!("{:#?}", file),
+            Err(FsError::LoopDetected { ancestor, child }) => {
+                eprintln!("loop: {child:?} -> {ancestor:?}")
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    // Classic indented tree listing
+    println!("{}", examples_folder.files().tree().render()?);
+
     // If you want to see each child file
     if let FileType::Directory(ref children) = examples_folder.file_type {
         for child in children {
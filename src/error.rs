@@ -0,0 +1,55 @@
+use std::{io, path::PathBuf};
+
+/// Errors that can occur while building a [`File`](crate::File) tree or
+/// while walking one with [`FilesIter`](crate::iter::FilesIter).
+#[derive(Debug)]
+pub enum FsError {
+    /// Wraps an I/O error produced while reading metadata or directory
+    /// entries from the filesystem.
+    Io(io::Error),
+    /// Following symlinks led back to a directory that is already an
+    /// ancestor of itself on the current path, i.e. a symlink loop.
+    LoopDetected {
+        /// The ancestor directory the symlink target resolves back to.
+        ancestor: PathBuf,
+        /// The symlink (or its target) that closes the loop.
+        child: PathBuf,
+    },
+}
+
+impl std::fmt::Display for FsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FsError::Io(err) => write!(f, "io error: {err}"),
+            FsError::LoopDetected { ancestor, child } => write!(
+                f,
+                "symlink loop detected: `{}` points back to ancestor `{}`",
+                child.display(),
+                ancestor.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FsError {}
+
+impl PartialEq for FsError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // `io::Error` isn't `PartialEq`; comparing by `kind()` is enough
+            // for tests to assert on the flavor of I/O failure they expect.
+            (FsError::Io(a), FsError::Io(b)) => a.kind() == b.kind(),
+            (
+                FsError::LoopDetected { ancestor: a1, child: c1 },
+                FsError::LoopDetected { ancestor: a2, child: c2 },
+            ) => a1 == a2 && c1 == c2,
+            _ => false,
+        }
+    }
+}
+
+impl From<io::Error> for FsError {
+    fn from(err: io::Error) -> Self {
+        FsError::Io(err)
+    }
+}
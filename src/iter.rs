@@ -1,19 +1,247 @@
-use crate::{File, FileType};
+use crate::{File, FileType, FsError};
 
-use std::{collections::VecDeque, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
-#[derive(Debug, Clone)]
+/// A small shell-style glob matcher, just enough to back `ignore` without
+/// pulling in an external glob crate.
+///
+/// Supports `*` (any run of characters), `?` (any single character) and
+/// `[...]` character classes (with `!` negation and `a-z` ranges).
+mod glob {
+    /// Returns whether `text` matches `pattern`.
+    pub(super) fn glob_match(pattern: &str, text: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        matches(&pattern, &text)
+    }
+
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        // Backtracking matcher: on a `*`, remember where we are in both
+        // slices so that, on a later mismatch, we can retry by having `*`
+        // swallow one more character of `text`.
+        let (mut pi, mut ti) = (0, 0);
+        let (mut star_pi, mut star_ti) = (None, 0);
+
+        while ti < text.len() {
+            if pi < pattern.len() && pattern[pi] == '*' {
+                star_pi = Some(pi);
+                star_ti = ti;
+                pi += 1;
+            } else if pi < pattern.len() && matches_one(pattern, &mut pi, text[ti]) {
+                ti += 1;
+            } else if let Some(sp) = star_pi {
+                pi = sp + 1;
+                star_ti += 1;
+                ti = star_ti;
+            } else {
+                return false;
+            }
+        }
+
+        while pi < pattern.len() && pattern[pi] == '*' {
+            pi += 1;
+        }
+
+        pi == pattern.len()
+    }
+
+    /// Tries to consume one pattern "atom" (a literal, `?`, or a `[...]`
+    /// class) against `ch`, advancing `pi` past it on success.
+    fn matches_one(pattern: &[char], pi: &mut usize, ch: char) -> bool {
+        match pattern[*pi] {
+            '?' => {
+                *pi += 1;
+                true
+            }
+            '[' => match find_class_end(pattern, *pi) {
+                Some(end) => {
+                    let matched = class_matches(&pattern[*pi + 1..end], ch);
+                    *pi = end + 1;
+                    matched
+                }
+                None => {
+                    let matched = pattern[*pi] == ch;
+                    *pi += 1;
+                    matched
+                }
+            },
+            literal => {
+                let matched = literal == ch;
+                *pi += 1;
+                matched
+            }
+        }
+    }
+
+    fn find_class_end(pattern: &[char], start: usize) -> Option<usize> {
+        pattern
+            .iter()
+            .enumerate()
+            .skip(start + 1)
+            .find(|(_, &c)| c == ']')
+            .map(|(i, _)| i)
+    }
+
+    fn class_matches(class: &[char], ch: char) -> bool {
+        let (negate, class) = match class.first() {
+            Some('!') => (true, &class[1..]),
+            _ => (false, class),
+        };
+
+        let mut found = false;
+        let mut i = 0;
+        while i < class.len() {
+            if i + 2 < class.len() && class[i + 1] == '-' {
+                if class[i] <= ch && ch <= class[i + 2] {
+                    found = true;
+                }
+                i += 3;
+            } else {
+                if class[i] == ch {
+                    found = true;
+                }
+                i += 1;
+            }
+        }
+
+        found != negate
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::glob_match;
+
+        #[test]
+        fn literal() {
+            assert!(glob_match("file.txt", "file.txt"));
+            assert!(!glob_match("file.txt", "file.md"));
+        }
+
+        #[test]
+        fn star() {
+            assert!(glob_match("*.tmp", "scratch.tmp"));
+            assert!(glob_match("*.tmp", ".tmp"));
+            assert!(!glob_match("*.tmp", "scratch.tmp.bak"));
+            assert!(glob_match("node_modules", "node_modules"));
+        }
+
+        #[test]
+        fn question_mark() {
+            assert!(glob_match("file?.rs", "file1.rs"));
+            assert!(!glob_match("file?.rs", "file12.rs"));
+        }
+
+        #[test]
+        fn character_class() {
+            assert!(glob_match("[abc].rs", "a.rs"));
+            assert!(!glob_match("[abc].rs", "d.rs"));
+            assert!(glob_match("[a-z].rs", "m.rs"));
+            assert!(glob_match("[!a-z].rs", "M.rs"));
+        }
+
+        #[test]
+        fn dotfile() {
+            assert!(glob_match(".*", ".git"));
+            assert!(!glob_match(".*", "git"));
+        }
+    }
+}
+
+/// A canonical identity for a directory, used to detect symlink loops while
+/// following symlinks.
+///
+/// On platforms that expose inode numbers we key on `(device, inode)`, which
+/// is loop-proof even across bind mounts and renames. Elsewhere we fall back
+/// to comparing canonicalized paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AncestorKey {
+    #[cfg(unix)]
+    Inode { dev: u64, ino: u64 },
+    Path(PathBuf),
+}
+
+impl AncestorKey {
+    fn for_path(path: &Path) -> Option<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            if let Ok(metadata) = std::fs::metadata(path) {
+                return Some(AncestorKey::Inode {
+                    dev: metadata.dev(),
+                    ino: metadata.ino(),
+                });
+            }
+        }
+
+        std::fs::canonicalize(path).ok().map(AncestorKey::Path)
+    }
+}
+
+/// Whether a directory entry sitting in the deque still needs its children
+/// pushed, or has already had that done and is just waiting to be emitted.
+///
+/// Only meaningful with `contents_first`: a directory is popped once to push
+/// its children (tagged `Unexpanded` the first time), then popped a second
+/// time, tagged `Expanded`, once every child has already been yielded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExpansionState {
+    Unexpanded,
+    Expanded,
+}
+
+/// Sibling comparator installed by [`FilesIter::sort_by`].
+type SortFn<'a> = Rc<dyn Fn(&File, &File) -> Ordering + 'a>;
+
+/// Subtree predicate installed by [`FilesIter::filter_entry`].
+type FilterEntryFn<'a> = Rc<dyn Fn(&File) -> bool + 'a>;
+
+#[derive(Clone)]
 pub struct FilesIter<'a> {
     // Directories go at the back, files at the front
-    // Has a aditional field for keeping track of depth
-    pub(crate) file_deque: VecDeque<(&'a File, usize)>,
+    // Has a aditional field for keeping track of depth, the chain of
+    // ancestor directories leading down to this entry (used for symlink loop
+    // detection when `follow_symlinks` is enabled), and whether a directory
+    // still needs expanding (used by `contents_first`)
+    pub(crate) file_deque: VecDeque<(&'a File, usize, Vec<AncestorKey>, ExpansionState)>,
     // Options
     pub(crate) files_before_directories: bool,
+    pub(crate) contents_first: bool,
     pub(crate) skip_dirs: bool,
     pub(crate) skip_regular_files: bool,
     pub(crate) skip_symlinks: bool,
+    pub(crate) follow_symlinks: bool,
     pub(crate) min_depth: usize,
     pub(crate) max_depth: usize,
+    // `Rc`, rather than `Box`, so `FilesIter` can stay `Clone`
+    pub(crate) sort_by: Option<SortFn<'a>>,
+    pub(crate) filter_entry: Option<FilterEntryFn<'a>>,
+    pub(crate) ignore_patterns: Vec<String>,
+}
+
+// Hand-written rather than derived: `sort_by`/`filter_entry` are `dyn Fn`
+// trait objects, which don't implement `Debug`.
+impl std::fmt::Debug for FilesIter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilesIter")
+            .field("file_deque", &self.file_deque)
+            .field("files_before_directories", &self.files_before_directories)
+            .field("contents_first", &self.contents_first)
+            .field("skip_dirs", &self.skip_dirs)
+            .field("skip_regular_files", &self.skip_regular_files)
+            .field("skip_symlinks", &self.skip_symlinks)
+            .field("follow_symlinks", &self.follow_symlinks)
+            .field("min_depth", &self.min_depth)
+            .field("max_depth", &self.max_depth)
+            .field("sort_by", &self.sort_by.as_ref().map(|_| "Fn(&File, &File) -> Ordering"))
+            .field("filter_entry", &self.filter_entry.as_ref().map(|_| "Fn(&File) -> bool"))
+            .field("ignore_patterns", &self.ignore_patterns)
+            .finish()
+    }
 }
 
 impl<'a> FilesIter<'a> {
@@ -25,12 +253,33 @@ impl<'a> FilesIter<'a> {
         }
     }
 
+    /// Adapts this iterator into a [`TreeDisplay`], which renders the
+    /// classic indented `tree`-style listing, reusing whatever filters were
+    /// set up here.
+    pub fn tree(self) -> TreeDisplay<'a> {
+        TreeDisplay {
+            file_iter: self,
+            hide_empty_dirs: false,
+        }
+    }
+
     // -- from here, only filters --
     pub fn files_before_directories(mut self, arg: bool) -> Self {
         self.files_before_directories = arg;
         self
     }
 
+    /// Yield a directory only after all of its descendants have already been
+    /// yielded, instead of the default leaves-after-parent order.
+    ///
+    /// This is what a bottom-up pass (e.g. a recursive delete, or a
+    /// directory-size aggregation) needs: by the time a directory is
+    /// produced, every entry under it has already gone by.
+    pub fn contents_first(mut self, arg: bool) -> Self {
+        self.contents_first = arg;
+        self
+    }
+
     pub fn skip_dirs(mut self, arg: bool) -> Self {
         self.skip_dirs = arg;
         self
@@ -46,6 +295,24 @@ impl<'a> FilesIter<'a> {
         self
     }
 
+    /// When a symlink resolves to a directory, descend into it and walk its
+    /// contents as if it were a real subdirectory.
+    ///
+    /// An ancestor set of canonicalized directories on the path down from the
+    /// root is maintained to detect loops: if a followed symlink resolves
+    /// back to one of its own ancestors, iteration yields
+    /// [`FsError::LoopDetected`] for that entry instead of recursing forever.
+    ///
+    /// Known tradeoff: each followed symlink's target is read afresh and its
+    /// children are leaked to satisfy this iterator's `'a` borrow (the rest
+    /// of it only ever borrows from the in-memory tree it started from). On
+    /// a process that repeatedly walks the same tree with this enabled, that
+    /// memory is never reclaimed.
+    pub fn follow_symlinks(mut self, arg: bool) -> Self {
+        self.follow_symlinks = arg;
+        self
+    }
+
     pub fn min_depth(mut self, min: usize) -> Self {
         self.min_depth = min;
         self
@@ -56,76 +323,324 @@ impl<'a> FilesIter<'a> {
         self
     }
 
+    /// Order each directory's children deterministically before they are
+    /// visited, instead of the filesystem/read order they were stored in.
+    ///
+    /// Essential for reproducible output across runs and platforms, e.g.
+    /// snapshot tests and diffable listings.
+    pub fn sort_by<F>(mut self, compare: F) -> Self
+    where
+        F: Fn(&File, &File) -> Ordering + 'a,
+    {
+        self.sort_by = Some(Rc::new(compare));
+        self
+    }
+
+    /// Shorthand for [`sort_by`](Self::sort_by) ordering siblings by their
+    /// file name.
+    pub fn sort_by_file_name(self) -> Self {
+        self.sort_by(|a, b| a.path.file_name().cmp(&b.path.file_name()))
+    }
+
+    /// Shorthand for [`sort_by`](Self::sort_by) ordering siblings by a
+    /// derived key.
+    pub fn sort_by_key<K, F>(self, key_fn: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&File) -> K + 'a,
+    {
+        self.sort_by(move |a, b| key_fn(a).cmp(&key_fn(b)))
+    }
+
+    /// Prune whole subtrees instead of merely hiding nodes from the output.
+    ///
+    /// When the predicate returns `false` for a directory, its children are
+    /// never pushed onto the walk at all (unlike `skip_dirs`, which still
+    /// descends and only withholds the directory itself). For non-directory
+    /// entries a `false` result simply skips emission. This composes with
+    /// the existing depth and type filters.
+    pub fn filter_entry<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&File) -> bool + 'a,
+    {
+        self.filter_entry = Some(Rc::new(predicate));
+        self
+    }
+
+    /// Exclude entries matching a shell-style glob pattern (e.g. `*.tmp`,
+    /// `target/`, `.*`), `.gitignore`-style. Repeatable: every pattern added
+    /// is tried.
+    ///
+    /// A pattern containing a path separator is matched against the entry's
+    /// full relative path; otherwise it's matched against just the final
+    /// path component. A pattern ending in `/` only matches directories. A
+    /// matching directory is pruned, same as [`filter_entry`](Self::filter_entry).
+    pub fn ignore(mut self, pattern: impl Into<String>) -> Self {
+        self.ignore_patterns.push(pattern.into());
+        self
+    }
+
+    fn is_ignored(&self, file: &File) -> bool {
+        let name = file.path.file_name().and_then(|name| name.to_str());
+        let full_path = file.path.to_str();
+
+        self.ignore_patterns.iter().any(|pattern| {
+            let only_dirs = pattern.ends_with('/');
+            if only_dirs && !file.file_type.is_dir() {
+                return false;
+            }
+            let pattern = pattern.strip_suffix('/').unwrap_or(pattern);
+
+            if pattern.contains('/') {
+                full_path.is_some_and(|full_path| glob::glob_match(pattern, full_path))
+            } else {
+                name.is_some_and(|name| glob::glob_match(pattern, name))
+            }
+        })
+    }
+
     // The only way to implement Default without exposing to public API:
     pub(crate) fn default() -> Self {
         FilesIter {
             file_deque: VecDeque::new(),
             // Options
             files_before_directories: false,
+            contents_first: false,
             skip_dirs: false,
             skip_regular_files: false,
             skip_symlinks: false,
+            follow_symlinks: false,
             min_depth: usize::MIN,
             max_depth: usize::MAX,
+            sort_by: None,
+            filter_entry: None,
+            ignore_patterns: Vec::new(),
         }
     }
-}
 
-impl<'a> Iterator for FilesIter<'a> {
-    type Item = &'a File;
+    /// Resolves a symlink's target, expanding it into `Directory` children to
+    /// walk through as if the symlink were a real subdirectory.
+    ///
+    /// Returns `Ok(None)` when the target isn't a directory (nothing to
+    /// expand), and `Err` when the target closes a loop back to one of
+    /// `ancestors`.
+    fn expand_followed_symlink(
+        file: &File,
+        ancestors: &[AncestorKey],
+    ) -> Result<Option<Vec<File>>, FsError> {
+        let target = std::fs::read_link(&file.path).map_err(FsError::Io)?;
+        let target = if target.is_absolute() {
+            target
+        } else {
+            file.path
+                .parent()
+                .map(|parent| parent.join(&target))
+                .unwrap_or(target)
+        };
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.file_deque.is_empty() {
-            return None;
+        if !target.is_dir() {
+            return Ok(None);
         }
 
-        // Pop from left or right?
-        //
-        // If self.files_before_directories is set, always pop from the left, where
-        // files are located
-        //
-        // Else, pop files from the right, that are directories, until there's no
-        // directory left, then start popping from the left
-        //
-        // Note: last_file_is_directory <-> there is a directory in the deque
-        let last_file_is_directory = self.file_deque.back().unwrap().0.file_type.is_dir();
-        let pop_from_the_left = self.files_before_directories || !last_file_is_directory;
+        let key = AncestorKey::for_path(&target);
+        if let Some(key) = &key {
+            if ancestors.contains(key) {
+                return Err(FsError::LoopDetected {
+                    ancestor: target.clone(),
+                    child: file.path.clone(),
+                });
+            }
+        }
 
-        // Unpack popped file and depth
-        let (file, depth) = if pop_from_the_left {
-            self.file_deque.pop_front()
-        } else {
-            self.file_deque.pop_back()
+        let resolved = File::<()>::new_from_path(&target, true)?;
+        match resolved.file_type {
+            FileType::Directory(children) => Ok(Some(children)),
+            _ => Ok(None),
         }
-        .unwrap();
-
-        // If directory, add children, and check for `self.skip_dirs`
-        if let FileType::Directory(ref children) = &file.file_type {
-            // Reversed, because late nodes stay at the tip
-            // We want at the tip the first ones
-            for child in children.iter().rev() {
-                if child.file_type.is_dir() {
-                    self.file_deque.push_back((child, depth + 1));
-                } else {
-                    self.file_deque.push_front((child, depth + 1));
+    }
+}
+
+impl<'a> FilesIter<'a> {
+    /// Like `next()`, but also hands back the depth of the yielded entry.
+    /// Used internally by `TreeDisplay`, which needs depth to render
+    /// indentation guides.
+    pub(crate) fn advance(&mut self) -> Option<Result<(&'a File, usize), FsError>> {
+        // A `loop` rather than the more obvious `return self.advance()`
+        // recursion on every skip path: a directory of N consecutively
+        // filtered/ignored siblings used to recurse N deep and blow the
+        // stack on large, heavily-filtered trees.
+        loop {
+            if self.file_deque.is_empty() {
+                return None;
+            }
+
+            // Pop from left or right?
+            //
+            // If self.files_before_directories is set, always pop from the left, where
+            // files are located
+            //
+            // Else, pop files from the right, that are directories, until there's no
+            // directory left, then start popping from the left
+            //
+            // Note: last_file_is_directory <-> there is a directory in the deque
+            let last_file_is_directory = self.file_deque.back().unwrap().0.file_type.is_dir();
+            // `contents_first` walks the deque purely as a stack (children
+            // always trail their parent at the back) so that a directory only
+            // resurfaces once every entry pushed after it has been drained.
+            let pop_from_the_left =
+                !self.contents_first && (self.files_before_directories || !last_file_is_directory);
+
+            // Unpack popped file, depth, ancestor chain and expansion state
+            let (file, depth, ancestors, state) = if pop_from_the_left {
+                self.file_deque.pop_front()
+            } else {
+                self.file_deque.pop_back()
+            }
+            .unwrap();
+
+            // `filter_entry` prunes whole subtrees: a directory that fails the
+            // predicate never gets its children pushed at all, unlike
+            // `skip_dirs`, which still descends. This check must run before
+            // expansion, and only on an entry's first visit (a contents-first
+            // directory's `Expanded` re-visit already passed it).
+            if state == ExpansionState::Unexpanded {
+                if let Some(predicate) = &self.filter_entry {
+                    if !predicate(file) {
+                        continue;
+                    }
+                }
+
+                if self.is_ignored(file) {
+                    continue;
                 }
             }
-        }
 
-        // If should skip due to depth limits
-        if self.min_depth > depth || self.max_depth < depth {
-            return self.next();
-        }
+            // If directory, add children, and check for `self.skip_dirs`
+            if let FileType::Directory(ref children) = &file.file_type {
+                if state == ExpansionState::Unexpanded {
+                    if self.contents_first {
+                        // Defer emission: push this directory back, tagged
+                        // `Expanded`, then push its children after it so they
+                        // get drained first and it only surfaces once every
+                        // descendant already has.
+                        self.file_deque.push_back((
+                            file,
+                            depth,
+                            ancestors.clone(),
+                            ExpansionState::Expanded,
+                        ));
+                        self.push_children(children.iter(), depth, &ancestors, file);
+                        continue;
+                    }
+                    self.push_children(children.iter(), depth, &ancestors, file);
+                }
+            } else if self.follow_symlinks
+                && file.file_type.is_symlink()
+                && state == ExpansionState::Unexpanded
+            {
+                match Self::expand_followed_symlink(file, &ancestors) {
+                    Ok(Some(children)) => {
+                        // `children` is owned, but the entries we push need to
+                        // live for `'a`: this mirrors the in-memory-tree-only
+                        // traversal the rest of the iterator assumes, so leak the
+                        // freshly-read subtree to get `&'a File`s out of it.
+                        let children: &'a [File] = Box::leak(children.into_boxed_slice());
+                        if self.contents_first {
+                            // Same discipline as a real directory: defer
+                            // emission of the symlink itself until every
+                            // entry pushed for its (re-read) target has
+                            // already been drained.
+                            self.file_deque.push_back((
+                                file,
+                                depth,
+                                ancestors.clone(),
+                                ExpansionState::Expanded,
+                            ));
+                            self.push_children(children.iter(), depth, &ancestors, file);
+                            continue;
+                        }
+                        self.push_children(children.iter(), depth, &ancestors, file);
+                    }
+                    Ok(None) => {}
+                    Err(err) => return Some(Err(err)),
+                }
+            }
 
-        // If should skip due file type specific skip filters
-        if self.skip_regular_files && file.file_type.is_regular()
-            || self.skip_dirs && file.file_type.is_dir()
-            || self.skip_dirs && file.file_type.is_dir()
-        {
-            return self.next();
+            // If should skip due to depth limits
+            if self.min_depth > depth || self.max_depth < depth {
+                continue;
+            }
+
+            // If should skip due file type specific skip filters
+            if self.skip_regular_files && file.file_type.is_regular()
+                || self.skip_dirs && file.file_type.is_dir()
+                || self.skip_symlinks && file.file_type.is_symlink()
+            {
+                continue;
+            }
+
+            return Some(Ok((file, depth)));
         }
+    }
+}
+
+impl<'a> Iterator for FilesIter<'a> {
+    type Item = Result<&'a File, FsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|result| result.map(|(file, _depth)| file))
+    }
+}
+
+impl<'a> FilesIter<'a> {
+    fn push_children<I>(&mut self, children: I, depth: usize, ancestors: &[AncestorKey], parent: &File)
+    where
+        I: DoubleEndedIterator<Item = &'a File>,
+    {
+        let child_ancestors = if self.follow_symlinks {
+            let mut chain = ancestors.to_vec();
+            if let Some(key) = AncestorKey::for_path(&parent.path) {
+                chain.push(key);
+            }
+            chain
+        } else {
+            Vec::new()
+        };
 
-        Some(&file)
+        // If a comparator was given, sort a collected list of references to
+        // the children before pushing, rather than trusting read/storage order.
+        let mut ordered: Vec<&'a File>;
+        let children: Box<dyn DoubleEndedIterator<Item = &'a File>> =
+            if let Some(compare) = self.sort_by.clone() {
+                ordered = children.collect();
+                ordered.sort_by(|a, b| compare(a, b));
+                Box::new(ordered.into_iter())
+            } else {
+                Box::new(children)
+            };
+
+        // Reversed, because late nodes stay at the tip
+        // We want at the tip the first ones
+        for child in children.rev() {
+            // `contents_first` needs every child, files included, to trail
+            // its parent on the same stack so the parent can't resurface
+            // before any of them have been drained.
+            if self.contents_first || child.file_type.is_dir() {
+                self.file_deque.push_back((
+                    child,
+                    depth + 1,
+                    child_ancestors.clone(),
+                    ExpansionState::Unexpanded,
+                ));
+            } else {
+                self.file_deque.push_front((
+                    child,
+                    depth + 1,
+                    child_ancestors.clone(),
+                    ExpansionState::Unexpanded,
+                ));
+            }
+        }
     }
 }
 
@@ -143,19 +658,146 @@ impl PathsIter<'_> {
         self.show_full_relative_path = arg;
         self
     }
+
+    /// See [`FilesIter::ignore`].
+    pub fn ignore(mut self, pattern: impl Into<String>) -> Self {
+        self.file_iter = self.file_iter.ignore(pattern);
+        self
+    }
 }
 
 impl<'a> Iterator for PathsIter<'a> {
-    type Item = &'a PathBuf;
+    type Item = Result<PathBuf, FsError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let next = self.file_iter.next()?;
+        let next = match self.file_iter.next()? {
+            Ok(next) => next,
+            Err(err) => return Some(Err(err)),
+        };
 
         if self.show_full_relative_path {
-            Some(&next.path)
+            Some(Ok(next.path.clone()))
         } else {
-            // Some(&next.path)
-            None
+            let name = next
+                .path
+                .file_name()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| next.path.clone());
+            Some(Ok(name))
+        }
+    }
+}
+
+/// Renders the classic `tree`-style indented listing, built from a
+/// [`FilesIter`] via [`FilesIter::tree`].
+///
+/// ```text
+/// .config
+/// ├── i3
+/// │   └── file1
+/// └── outerfile1
+/// ```
+pub struct TreeDisplay<'a> {
+    file_iter: FilesIter<'a>,
+    hide_empty_dirs: bool,
+}
+
+impl TreeDisplay<'_> {
+    /// Suppress directories that ended up with no entries under them after
+    /// the iterator's filters were applied.
+    pub fn hide_empty_dirs(mut self, arg: bool) -> Self {
+        self.hide_empty_dirs = arg;
+        self
+    }
+
+    /// Renders the tree to a `String`, one entry per line.
+    pub fn render(mut self) -> Result<String, FsError> {
+        let mut entries = Vec::new();
+        while let Some(next) = self.file_iter.advance() {
+            entries.push(next?);
+        }
+
+        if self.hide_empty_dirs {
+            entries = Self::prune_empty_dirs(entries);
+        }
+
+        // An entry is the last child of its parent unless a later entry at
+        // the same depth turns up before the depth goes shallower again.
+        // `last_at_depth[d]` tracks the index of the most recent entry seen
+        // at depth `d`; truncating it on each step discards now-closed
+        // deeper branches.
+        let mut is_last = vec![true; entries.len()];
+        let mut last_at_depth: Vec<Option<usize>> = Vec::new();
+        for (i, &(_, depth)) in entries.iter().enumerate() {
+            last_at_depth.truncate(depth + 1);
+            if last_at_depth.len() <= depth {
+                last_at_depth.resize(depth + 1, None);
+            }
+            if let Some(previous) = last_at_depth[depth] {
+                is_last[previous] = false;
+            }
+            last_at_depth[depth] = Some(i);
+        }
+
+        // `ancestors_continue[d]` says whether the ancestor at depth `d + 1`
+        // still has more siblings coming, i.e. whether its line is a `│` or
+        // blank. The root has no guide of its own, so nothing is pushed for
+        // depth 0.
+        let mut rendered = String::new();
+        let mut ancestors_continue: Vec<bool> = Vec::new();
+        for (i, (file, depth)) in entries.iter().enumerate() {
+            ancestors_continue.truncate(depth.saturating_sub(1));
+            for &continues in &ancestors_continue {
+                rendered.push_str(if continues { "│   " } else { "    " });
+            }
+            if *depth > 0 {
+                rendered.push_str(if is_last[i] { "└── " } else { "├── " });
+            }
+
+            let name = file
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.path.to_string_lossy().into_owned());
+            rendered.push_str(&name);
+            rendered.push('\n');
+
+            if *depth > 0 {
+                ancestors_continue.push(!is_last[i]);
+            }
+        }
+
+        Ok(rendered)
+    }
+
+    /// Drops directories whose subtree, after filtering, has nothing left
+    /// under them. Run to a fixpoint so a directory left empty by dropping
+    /// its own now-empty children is dropped in turn.
+    fn prune_empty_dirs(mut entries: Vec<(&File, usize)>) -> Vec<(&File, usize)> {
+        loop {
+            let mut changed = false;
+            let keep: Vec<bool> = entries
+                .iter()
+                .enumerate()
+                .map(|(i, &(file, depth))| {
+                    if !file.file_type.is_dir() {
+                        return true;
+                    }
+                    let has_child = entries.get(i + 1).is_some_and(|&(_, d)| d > depth);
+                    changed |= !has_child;
+                    has_child
+                })
+                .collect();
+
+            if !changed {
+                return entries;
+            }
+            entries = entries
+                .into_iter()
+                .zip(keep)
+                .filter(|(_, keep)| *keep)
+                .map(|(entry, _)| entry)
+                .collect();
         }
     }
 }
@@ -166,6 +808,7 @@ mod tests {
     #[rustfmt::skip]
     fn testing_files_and_paths_iters() {
         use crate::{File, FileType::*};
+        use std::path::PathBuf;
 
         // Implementing a syntax sugar util to make tests readable
         impl File {
@@ -221,42 +864,86 @@ mod tests {
         ];
 
         let mut it = root.files();
-        assert_eq!(it.next(), Some(refs[0])); // .config/
-        assert_eq!(it.next(), Some(refs[1])); // .config/i3/
-        assert_eq!(it.next(), Some(refs[4])); // .config/i3/dir/
-        assert_eq!(it.next(), Some(refs[5])); // .config/i3/dir/innerfile1
-        assert_eq!(it.next(), Some(refs[6])); // .config/i3/dir/innerfile2
-        assert_eq!(it.next(), Some(refs[2])); // .config/i3/file1
-        assert_eq!(it.next(), Some(refs[3])); // .config/i3/file2
-        assert_eq!(it.next(), Some(refs[7])); // .config/i3/file3
-        assert_eq!(it.next(), Some(refs[8])); // .config/outerfile1
-        assert_eq!(it.next(), Some(refs[9])); // .config/outerfile2
+        assert_eq!(it.next(), Some(Ok(refs[0]))); // .config/
+        assert_eq!(it.next(), Some(Ok(refs[1]))); // .config/i3/
+        assert_eq!(it.next(), Some(Ok(refs[4]))); // .config/i3/dir/
+        assert_eq!(it.next(), Some(Ok(refs[5]))); // .config/i3/dir/innerfile1
+        assert_eq!(it.next(), Some(Ok(refs[6]))); // .config/i3/dir/innerfile2
+        assert_eq!(it.next(), Some(Ok(refs[2]))); // .config/i3/file1
+        assert_eq!(it.next(), Some(Ok(refs[3]))); // .config/i3/file2
+        assert_eq!(it.next(), Some(Ok(refs[7]))); // .config/i3/file3
+        assert_eq!(it.next(), Some(Ok(refs[8]))); // .config/outerfile1
+        assert_eq!(it.next(), Some(Ok(refs[9]))); // .config/outerfile2
 
         let mut it = root.files().files_before_directories(true);
-        assert_eq!(it.next(), Some(refs[0])); // .config/
-        assert_eq!(it.next(), Some(refs[8])); // .config/outerfile1
-        assert_eq!(it.next(), Some(refs[9])); // .config/outerfile2
-        assert_eq!(it.next(), Some(refs[1])); // .config/i3/
-        assert_eq!(it.next(), Some(refs[2])); // .config/i3/file1
-        assert_eq!(it.next(), Some(refs[3])); // .config/i3/file2
-        assert_eq!(it.next(), Some(refs[7])); // .config/i3/file3
-        assert_eq!(it.next(), Some(refs[4])); // .config/i3/dir/
-        assert_eq!(it.next(), Some(refs[5])); // .config/i3/dir/innerfile1
-        assert_eq!(it.next(), Some(refs[6])); // .config/i3/dir/innerfile2
+        assert_eq!(it.next(), Some(Ok(refs[0]))); // .config/
+        assert_eq!(it.next(), Some(Ok(refs[8]))); // .config/outerfile1
+        assert_eq!(it.next(), Some(Ok(refs[9]))); // .config/outerfile2
+        assert_eq!(it.next(), Some(Ok(refs[1]))); // .config/i3/
+        assert_eq!(it.next(), Some(Ok(refs[2]))); // .config/i3/file1
+        assert_eq!(it.next(), Some(Ok(refs[3]))); // .config/i3/file2
+        assert_eq!(it.next(), Some(Ok(refs[7]))); // .config/i3/file3
+        assert_eq!(it.next(), Some(Ok(refs[4]))); // .config/i3/dir/
+        assert_eq!(it.next(), Some(Ok(refs[5]))); // .config/i3/dir/innerfile1
+        assert_eq!(it.next(), Some(Ok(refs[6]))); // .config/i3/dir/innerfile2
 
         let mut it = root.files().skip_dirs(true);
-        assert_eq!(it.next(), Some(refs[5])); // .config/i3/dir/innerfile1
-        assert_eq!(it.next(), Some(refs[6])); // .config/i3/dir/innerfile2
-        assert_eq!(it.next(), Some(refs[2])); // .config/i3/file1
-        assert_eq!(it.next(), Some(refs[3])); // .config/i3/file2
-        assert_eq!(it.next(), Some(refs[7])); // .config/i3/file3
-        assert_eq!(it.next(), Some(refs[8])); // .config/outerfile1
-        assert_eq!(it.next(), Some(refs[9])); // .config/outerfile2
+        assert_eq!(it.next(), Some(Ok(refs[5]))); // .config/i3/dir/innerfile1
+        assert_eq!(it.next(), Some(Ok(refs[6]))); // .config/i3/dir/innerfile2
+        assert_eq!(it.next(), Some(Ok(refs[2]))); // .config/i3/file1
+        assert_eq!(it.next(), Some(Ok(refs[3]))); // .config/i3/file2
+        assert_eq!(it.next(), Some(Ok(refs[7]))); // .config/i3/file3
+        assert_eq!(it.next(), Some(Ok(refs[8]))); // .config/outerfile1
+        assert_eq!(it.next(), Some(Ok(refs[9]))); // .config/outerfile2
 
         let mut it = root.files().skip_regular_files(true);
-        assert_eq!(it.next(), Some(refs[0])); // .config/
-        assert_eq!(it.next(), Some(refs[1])); // .config/i3/
-        assert_eq!(it.next(), Some(refs[4])); // .config/i3/dir/
+        assert_eq!(it.next(), Some(Ok(refs[0]))); // .config/
+        assert_eq!(it.next(), Some(Ok(refs[1]))); // .config/i3/
+        assert_eq!(it.next(), Some(Ok(refs[4]))); // .config/i3/dir/
+
+        let mut it = root.files().contents_first(true);
+        assert_eq!(it.next(), Some(Ok(refs[2]))); // .config/i3/file1
+        assert_eq!(it.next(), Some(Ok(refs[3]))); // .config/i3/file2
+        assert_eq!(it.next(), Some(Ok(refs[5]))); // .config/i3/dir/innerfile1
+        assert_eq!(it.next(), Some(Ok(refs[6]))); // .config/i3/dir/innerfile2
+        assert_eq!(it.next(), Some(Ok(refs[4]))); // .config/i3/dir/
+        assert_eq!(it.next(), Some(Ok(refs[7]))); // .config/i3/file3
+        assert_eq!(it.next(), Some(Ok(refs[1]))); // .config/i3/
+        assert_eq!(it.next(), Some(Ok(refs[8]))); // .config/outerfile1
+        assert_eq!(it.next(), Some(Ok(refs[9]))); // .config/outerfile2
+        assert_eq!(it.next(), Some(Ok(refs[0]))); // .config/
+
+        // sort_by_file_name fixes up storage order into alphabetical order,
+        // regardless of how the tree was built
+        #[rustfmt::skip]
+        let unsorted = File::new("root", Directory(vec![
+            File::new("c", Regular),
+            File::new("a", Regular),
+            File::new("b", Regular),
+        ]));
+        let names: Vec<_> = unsorted
+            .files()
+            .sort_by_file_name()
+            .skip(1) // skip the root itself
+            .map(|file| file.unwrap().path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+
+        // filter_entry prunes a whole subtree, unlike skip_dirs which still
+        // descends into it
+        let mut it = root
+            .files()
+            .filter_entry(|file| file.path.file_name().and_then(|n| n.to_str()) != Some("i3"));
+        assert_eq!(it.next(), Some(Ok(refs[0]))); // .config/
+        assert_eq!(it.next(), Some(Ok(refs[8]))); // .config/outerfile1
+        assert_eq!(it.next(), Some(Ok(refs[9]))); // .config/outerfile2
+        assert_eq!(it.next(), None);
+
+        // ignore prunes matching entries by glob, same as filter_entry
+        let mut it = root.files().ignore("i3").ignore("*2");
+        assert_eq!(it.next(), Some(Ok(refs[0]))); // .config/
+        assert_eq!(it.next(), Some(Ok(refs[8]))); // .config/outerfile1
+        assert_eq!(it.next(), None);
 
         // min and max depth (1 <= d <= 2)
         //
@@ -265,27 +952,94 @@ mod tests {
         // .config/i3/dir/innerfile1
         // .config/i3/dir/innerfile2
         let mut it = root.files().min_depth(1).max_depth(2);
-        assert_eq!(it.next(), Some(refs[1])); // .config/i3/
-        assert_eq!(it.next(), Some(refs[4])); // .config/i3/dir/
-        assert_eq!(it.next(), Some(refs[2])); // .config/i3/file1
-        assert_eq!(it.next(), Some(refs[3])); // .config/i3/file2
-        assert_eq!(it.next(), Some(refs[7])); // .config/i3/file3
-        assert_eq!(it.next(), Some(refs[8])); // .config/outerfile1
-        assert_eq!(it.next(), Some(refs[9])); // .config/outerfile2
+        assert_eq!(it.next(), Some(Ok(refs[1]))); // .config/i3/
+        assert_eq!(it.next(), Some(Ok(refs[4]))); // .config/i3/dir/
+        assert_eq!(it.next(), Some(Ok(refs[2]))); // .config/i3/file1
+        assert_eq!(it.next(), Some(Ok(refs[3]))); // .config/i3/file2
+        assert_eq!(it.next(), Some(Ok(refs[7]))); // .config/i3/file3
+        assert_eq!(it.next(), Some(Ok(refs[8]))); // .config/outerfile1
+        assert_eq!(it.next(), Some(Ok(refs[9]))); // .config/outerfile2
 
         // ---------------------
         //
         // Paths iterator testing
         let mut it = root.paths();
-        assert_eq!(it.next(), Some(&refs[0].path)); // ".config/"
-        assert_eq!(it.next(), Some(&refs[1].path)); // ".config/i3/"
-        assert_eq!(it.next(), Some(&refs[4].path)); // ".config/i3/dir/"
-        assert_eq!(it.next(), Some(&refs[5].path)); // ".config/i3/dir/innerfile1"
-        assert_eq!(it.next(), Some(&refs[6].path)); // ".config/i3/dir/innerfile2"
-        assert_eq!(it.next(), Some(&refs[2].path)); // ".config/i3/file1"
-        assert_eq!(it.next(), Some(&refs[3].path)); // ".config/i3/file2"
-        assert_eq!(it.next(), Some(&refs[7].path)); // ".config/i3/file3"
-        assert_eq!(it.next(), Some(&refs[8].path)); // ".config/outerfile1"
-        assert_eq!(it.next(), Some(&refs[9].path)); // ".config/outerfile2"
+        assert_eq!(it.next(), Some(Ok(refs[0].path.clone()))); // ".config/"
+        assert_eq!(it.next(), Some(Ok(refs[1].path.clone()))); // ".config/i3/"
+        assert_eq!(it.next(), Some(Ok(refs[4].path.clone()))); // ".config/i3/dir/"
+        assert_eq!(it.next(), Some(Ok(refs[5].path.clone()))); // ".config/i3/dir/innerfile1"
+        assert_eq!(it.next(), Some(Ok(refs[6].path.clone()))); // ".config/i3/dir/innerfile2"
+        assert_eq!(it.next(), Some(Ok(refs[2].path.clone()))); // ".config/i3/file1"
+        assert_eq!(it.next(), Some(Ok(refs[3].path.clone()))); // ".config/i3/file2"
+        assert_eq!(it.next(), Some(Ok(refs[7].path.clone()))); // ".config/i3/file3"
+        assert_eq!(it.next(), Some(Ok(refs[8].path.clone()))); // ".config/outerfile1"
+        assert_eq!(it.next(), Some(Ok(refs[9].path.clone()))); // ".config/outerfile2"
+
+        // Name-only mode yields just the final path component
+        let mut it = root.paths().show_full_relative_path(false);
+        assert_eq!(it.next(), Some(Ok(PathBuf::from(".config"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("i3"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("dir"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("innerfile1"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("innerfile2"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("file1"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("file2"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("file3"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("outerfile1"))));
+        assert_eq!(it.next(), Some(Ok(PathBuf::from("outerfile2"))));
+
+        // tree() renders the classic indented listing, driven by depth and
+        // last-child tracking
+        let rendered = root.files().tree().render().unwrap();
+        assert_eq!(
+            rendered,
+            concat!(
+                ".config\n",
+                "├── i3\n",
+                "│   ├── dir\n",
+                "│   │   ├── innerfile1\n",
+                "│   │   └── innerfile2\n",
+                "│   ├── file1\n",
+                "│   ├── file2\n",
+                "│   └── file3\n",
+                "├── outerfile1\n",
+                "└── outerfile2\n",
+            )
+        );
+    }
+
+    /// `follow_symlinks` over a real mutual symlink loop (`a/to_b -> b`,
+    /// `b/to_a -> a`) must surface `FsError::LoopDetected` instead of
+    /// recursing forever.
+    #[cfg(unix)]
+    #[test]
+    fn follow_symlinks_detects_loop() {
+        use crate::{File, FsError};
+        use std::os::unix::fs::symlink;
+
+        let root = std::env::temp_dir().join(format!(
+            "file_structure_follow_symlinks_loop_{}_{}",
+            std::process::id(),
+            line!()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::create_dir_all(root.join("b")).unwrap();
+        symlink(root.join("b"), root.join("a/to_b")).unwrap();
+        symlink(root.join("a"), root.join("b/to_a")).unwrap();
+
+        let tree = File::<()>::new_from_path(&root, true).unwrap();
+        let errors: Vec<_> = tree
+            .files()
+            .follow_symlinks(true)
+            .filter_map(|entry| entry.err())
+            .collect();
+
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(
+            errors.iter().any(|err| matches!(err, FsError::LoopDetected { .. })),
+            "expected a LoopDetected error, got {errors:?}"
+        );
     }
 }
@@ -0,0 +1,182 @@
+//! Parallel directory reading, used by `File::new_from_path_parallel`.
+//!
+//! `File::new_from_path` reads the whole tree on a single thread, which is
+//! dominated by `stat`/`readdir` latency rather than CPU on large trees.
+//! This fans subdirectory reads out across a bounded pool of scoped threads
+//! and merges the results back in a stable order, matching what
+//! `new_from_path` would have produced serially.
+
+use crate::{File, FileType, FsError};
+
+use std::{
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+impl<T> File<T>
+where
+    T: Default + Clone + Send,
+{
+    /// Like [`File::new_from_path`], but fans subdirectory reads out across
+    /// up to `workers` scoped threads instead of reading the tree on a
+    /// single one.
+    ///
+    /// Worth reaching for on large trees where traversal time is dominated
+    /// by `stat`/`readdir` latency (spinning disks, networked filesystems)
+    /// rather than by CPU.
+    pub fn new_from_path_parallel(path: impl AsRef<Path>, workers: usize) -> Result<Self, FsError> {
+        read_dir_parallel(path.as_ref(), workers)
+    }
+}
+
+/// Reads `path` the same way `File::new_from_path` does, but recurses into
+/// subdirectories across up to `workers` threads instead of one.
+///
+/// Concurrency is bounded by a counting gate rather than an unbounded
+/// thread-per-directory fan-out: a child only gets its own scoped thread
+/// when a worker slot is free, and is read inline on the current thread
+/// otherwise. This keeps the number of concurrently open file descriptors
+/// bounded, which matters on wide trees.
+pub(crate) fn read_dir_parallel<T>(path: &Path, workers: usize) -> Result<File<T>, FsError>
+where
+    T: Default + Clone + Send,
+{
+    let available = AtomicUsize::new(workers.max(1));
+    // Goes through `read_entry`, same as every nested child, so a root that
+    // is itself a symlink is read as a `FileType::Symlink` leaf rather than
+    // unconditionally treated as a directory to read.
+    read_entry(path, &available)
+}
+
+enum Child<'scope, T> {
+    Spawned(std::thread::ScopedJoinHandle<'scope, Result<File<T>, FsError>>),
+    Inline(Result<File<T>, FsError>),
+}
+
+fn read_dir<T>(path: &Path, available: &AtomicUsize) -> Result<File<T>, FsError>
+where
+    T: Default + Clone + Send,
+{
+    let entry_paths: Vec<_> = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+
+    let children = std::thread::scope(|scope| {
+        let handles: Vec<_> = entry_paths
+            .into_iter()
+            .map(|entry_path| {
+                if try_acquire(available) {
+                    Child::Spawned(scope.spawn(move || {
+                        let result = read_entry(&entry_path, available);
+                        available.fetch_add(1, Ordering::Relaxed);
+                        result
+                    }))
+                } else {
+                    Child::Inline(read_entry(&entry_path, available))
+                }
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|child| match child {
+                Child::Spawned(handle) => handle.join().expect("worker thread panicked"),
+                Child::Inline(result) => result,
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    let mut root = File::<T>::new_from_path(path, false)?;
+    root.file_type = FileType::Directory(children);
+    Ok(root)
+}
+
+fn read_entry<T>(path: &Path, available: &AtomicUsize) -> Result<File<T>, FsError>
+where
+    T: Default + Clone + Send,
+{
+    // `symlink_metadata` rather than `path.is_dir()`/`path.metadata()`: both
+    // of those follow symlinks, which would silently expand any symlink
+    // pointing at a directory into a real subdirectory (a different tree
+    // shape than the serial constructor produces, and with no loop
+    // detection to guard against a symlink cycle sending this into
+    // unbounded recursion and thread spawns).
+    let is_real_dir = std::fs::symlink_metadata(path).map(|meta| meta.is_dir())?;
+
+    if is_real_dir {
+        read_dir(path, available)
+    } else {
+        File::<T>::new_from_path(path, false)
+    }
+}
+
+/// Attempts to reserve one worker slot, returning whether it succeeded.
+fn try_acquire(available: &AtomicUsize) -> bool {
+    available
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+            (n > 0).then(|| n - 1)
+        })
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `new_from_path_parallel` must produce the same set of paths as the
+    /// serial `new_from_path` for the same tree.
+    #[test]
+    fn parallel_matches_serial() {
+        let root = std::env::temp_dir().join(format!(
+            "file_structure_parallel_matches_serial_{}_{}",
+            std::process::id(),
+            line!()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::create_dir_all(root.join("c")).unwrap();
+        std::fs::write(root.join("a/file1"), b"x").unwrap();
+        std::fs::write(root.join("a/b/file2"), b"y").unwrap();
+        std::fs::write(root.join("c/file3"), b"z").unwrap();
+
+        let serial = File::<()>::new_from_path(&root, true).unwrap();
+        let parallel = File::<()>::new_from_path_parallel(&root, 4).unwrap();
+
+        let mut serial_paths: Vec<_> = serial.files().map(|f| f.unwrap().path.clone()).collect();
+        let mut parallel_paths: Vec<_> = parallel.files().map(|f| f.unwrap().path.clone()).collect();
+        serial_paths.sort();
+        parallel_paths.sort();
+
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(serial_paths, parallel_paths);
+    }
+
+    /// A root path that is itself a symlink to a directory must be read as a
+    /// `FileType::Symlink` leaf, same as the serial constructor, rather than
+    /// unconditionally expanded into a directory.
+    #[cfg(unix)]
+    #[test]
+    fn parallel_root_symlink_matches_serial() {
+        use std::os::unix::fs::symlink;
+
+        let base = std::env::temp_dir().join(format!(
+            "file_structure_parallel_root_symlink_{}_{}",
+            std::process::id(),
+            line!()
+        ));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(base.join("real")).unwrap();
+        std::fs::write(base.join("real/file1"), b"x").unwrap();
+        let link = base.join("link_to_real");
+        symlink(base.join("real"), &link).unwrap();
+
+        let serial = File::<()>::new_from_path(&link, true).unwrap();
+        let parallel = File::<()>::new_from_path_parallel(&link, 4).unwrap();
+
+        std::fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(serial.file_type.is_symlink(), parallel.file_type.is_symlink());
+        assert!(parallel.file_type.is_symlink());
+    }
+}